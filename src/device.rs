@@ -4,6 +4,8 @@ use onvif::{schema::{self, transport, onvif::User}, soap};
 use url::Url;
 use log::*;
 
+use crate::util;
+
 #[derive(Debug)]
 pub enum DeviceError {
     /// Device acted in an unexpected way.
@@ -62,6 +64,27 @@ impl std::fmt::Display for DeviceError {
 
 impl std::error::Error for DeviceError {}
 
+/// A requested IPv4 configuration for `Device::set_network_interface`.
+#[derive(Debug, Clone)]
+pub enum Ipv4Config {
+    /// Enable DHCP for this interface.
+    Dhcp,
+    /// Assign a manual address and prefix length.
+    Manual { address: String, prefix_length: i32 },
+}
+
+/// A media profile's video configuration and resolved RTSP stream URL.
+#[derive(Debug, Clone)]
+pub struct StreamInfo {
+    pub profile_token: String,
+    pub profile_name: String,
+    pub encoding: Option<String>,
+    pub resolution: Option<(i32, i32)>,
+    pub framerate: Option<f64>,
+    pub bitrate: Option<i32>,
+    pub uri: String,
+}
+
 pub struct Device {
     devicemgmt: soap::client::Client,
     event: Option<soap::client::Client>,
@@ -149,8 +172,125 @@ impl Device {
         return self.devicemgmt.clone();
     }
 
-    pub fn get_media_service(&self) -> Option<soap::client::Client> {
-        return self.media.clone();
+    fn get_ptz_service(&self) -> Result<soap::client::Client, DeviceError> {
+        return self.ptz.clone().ok_or_else(|| DeviceError::UnexpectedBehavior("Device does not advertise a PTZ service".into()));
+    }
+
+    /// Token of the first media profile, used as the PTZ target since this
+    /// tool doesn't otherwise expose profile selection for PTZ operations.
+    async fn first_profile_token(&self) -> Result<schema::onvif::ReferenceToken, DeviceError> {
+        if let Some(media2) = &self.media2 {
+            let profiles = schema::media2::get_profiles(media2, &Default::default()).await?.profiles;
+            let profile = profiles.into_iter().next()
+                .ok_or_else(|| DeviceError::UnexpectedBehavior("Device has no media profiles".into()))?;
+            return Ok(profile.token);
+        }
+        if let Some(media) = &self.media {
+            let profiles = schema::media::get_profiles(media, &Default::default()).await?.profiles;
+            let profile = profiles.into_iter().next()
+                .ok_or_else(|| DeviceError::UnexpectedBehavior("Device has no media profiles".into()))?;
+            return Ok(profile.token);
+        }
+        return Err(DeviceError::UnexpectedBehavior("Device does not advertise a media service".into()));
+    }
+
+    pub async fn ptz_continuous_move(&self, pan: f32, tilt: f32, zoom: f32, speed: f32) -> Result<(), DeviceError> {
+        let ptz = self.get_ptz_service()?;
+        let profile_token = self.first_profile_token().await?;
+
+        schema::ptz::continuous_move(&ptz, &schema::ptz::ContinuousMove {
+            profile_token,
+            velocity: schema::onvif::PtzSpeed {
+                pan_tilt: Some(schema::onvif::Vector2D { x: pan * speed, y: tilt * speed, space: None }),
+                zoom: Some(schema::onvif::Vector1D { x: zoom * speed, space: None }),
+            },
+            timeout: None,
+        }).await?;
+
+        return Ok(());
+    }
+
+    pub async fn ptz_relative_move(&self, pan: f32, tilt: f32, zoom: f32) -> Result<(), DeviceError> {
+        let ptz = self.get_ptz_service()?;
+        let profile_token = self.first_profile_token().await?;
+
+        schema::ptz::relative_move(&ptz, &schema::ptz::RelativeMove {
+            profile_token,
+            translation: schema::onvif::PtzVector {
+                pan_tilt: Some(schema::onvif::Vector2D { x: pan, y: tilt, space: None }),
+                zoom: Some(schema::onvif::Vector1D { x: zoom, space: None }),
+            },
+            speed: None,
+        }).await?;
+
+        return Ok(());
+    }
+
+    pub async fn ptz_absolute_move(&self, pan: f32, tilt: f32, zoom: f32) -> Result<(), DeviceError> {
+        let ptz = self.get_ptz_service()?;
+        let profile_token = self.first_profile_token().await?;
+
+        schema::ptz::absolute_move(&ptz, &schema::ptz::AbsoluteMove {
+            profile_token,
+            position: schema::onvif::PtzVector {
+                pan_tilt: Some(schema::onvif::Vector2D { x: pan, y: tilt, space: None }),
+                zoom: Some(schema::onvif::Vector1D { x: zoom, space: None }),
+            },
+            speed: None,
+        }).await?;
+
+        return Ok(());
+    }
+
+    pub async fn ptz_stop(&self) -> Result<(), DeviceError> {
+        let ptz = self.get_ptz_service()?;
+        let profile_token = self.first_profile_token().await?;
+
+        schema::ptz::stop(&ptz, &schema::ptz::Stop {
+            profile_token,
+            pan_tilt: Some(true),
+            zoom: Some(true),
+        }).await?;
+
+        return Ok(());
+    }
+
+    pub async fn ptz_goto_preset(&self, preset_token: &str) -> Result<(), DeviceError> {
+        let ptz = self.get_ptz_service()?;
+        let profile_token = self.first_profile_token().await?;
+
+        schema::ptz::goto_preset(&ptz, &schema::ptz::GotoPreset {
+            profile_token,
+            preset_token: schema::onvif::ReferenceToken(preset_token.to_string()),
+            speed: None,
+        }).await?;
+
+        return Ok(());
+    }
+
+    pub async fn ptz_set_preset(&self, preset_name: &str) -> Result<String, DeviceError> {
+        let ptz = self.get_ptz_service()?;
+        let profile_token = self.first_profile_token().await?;
+
+        let response = schema::ptz::set_preset(&ptz, &schema::ptz::SetPreset {
+            profile_token,
+            preset_name: Some(preset_name.to_string()),
+            preset_token: None,
+        }).await?;
+
+        return Ok(response.preset_token.0);
+    }
+
+    pub async fn ptz_remove_preset(&self, preset_token: &str) -> Result<(), DeviceError> {
+        let ptz = self.get_ptz_service()?;
+        let profile_token = self.first_profile_token().await?;
+
+        schema::ptz::remove_preset(&ptz, &schema::ptz::RemovePreset {
+            profile_token,
+            preset_token: schema::onvif::ReferenceToken(preset_token.to_string()),
+        }).await?;
+
+        return Ok(());
     }
 
     pub async fn get_users(&self) -> Result<Vec<User>, DeviceError> {
@@ -176,5 +316,135 @@ impl Device {
         let x = schema::devicemgmt::system_reboot(&self.devicemgmt, &Default::default()).await?;
         return Ok(x.message);
     }
-    
+
+    /// Enumerate media profiles and resolve their RTSP stream URIs.
+    ///
+    /// Prefers the ver20 media2 service when the device advertised it,
+    /// falling back to ver10 media. Both read the video encoder
+    /// configuration from the profiles returned by `GetProfiles`.
+    pub async fn get_stream_uris(&self) -> Result<Vec<StreamInfo>, DeviceError> {
+        if let Some(media2) = &self.media2 {
+            return self.get_stream_uris_v20(media2).await;
+        }
+        if let Some(media) = &self.media {
+            return self.get_stream_uris_v10(media).await;
+        }
+        return Err(DeviceError::UnexpectedBehavior("Device does not advertise a media service".into()));
+    }
+
+    async fn get_stream_uris_v10(&self, media: &soap::client::Client) -> Result<Vec<StreamInfo>, DeviceError> {
+        let profiles = schema::media::get_profiles(media, &Default::default()).await?.profiles;
+
+        let mut streams = Vec::new();
+        for profile in profiles {
+            let stream_uri = schema::media::get_stream_uri(media, &schema::media::GetStreamUri {
+                stream_setup: schema::onvif::StreamSetup {
+                    stream: schema::onvif::StreamType::RtpUnicast,
+                    transport: schema::onvif::Transport {
+                        protocol: schema::onvif::TransportProtocol::Rtsp,
+                        tunnel: None,
+                    },
+                },
+                profile_token: profile.token.clone(),
+                ..Default::default()
+            }).await?;
+
+            let video = profile.video_encoder_configuration.as_ref();
+            streams.push(StreamInfo {
+                profile_token: profile.token.0.clone(),
+                profile_name: profile.name.clone(),
+                encoding: video.map(|v| format!("{:?}", v.encoding)),
+                resolution: video.map(|v| (v.resolution.width, v.resolution.height)),
+                framerate: video.and_then(|v| v.rate_control.as_ref()).map(|r| r.frame_rate_limit as f64),
+                bitrate: video.and_then(|v| v.rate_control.as_ref()).map(|r| r.bitrate_limit),
+                uri: stream_uri.media_uri.uri,
+            });
+        }
+
+        return Ok(streams);
+    }
+
+    pub async fn set_ntp(&self, from_dhcp: bool, ntp_manual: Vec<schema::onvif::NetworkHost>) -> Result<(), DeviceError> {
+        schema::devicemgmt::set_ntp(&self.devicemgmt, &schema::devicemgmt::SetNtp {
+            from_dhcp,
+            ntp_manual,
+        }).await?;
+        return Ok(());
+    }
+
+    pub async fn set_dns(&self, from_dhcp: bool, search_domain: Vec<String>, dns_manual: Vec<String>) -> Result<(), DeviceError> {
+        let dns_manual = dns_manual.into_iter().map(|address| match util::classify_ip(&address) {
+            Some(util::IpVersion::V4) => Ok(schema::onvif::IpAddress {
+                type_: schema::onvif::IpType::IPv4,
+                i_pv_4_address: Some(address),
+                i_pv_6_address: None,
+            }),
+            Some(util::IpVersion::V6) => Ok(schema::onvif::IpAddress {
+                type_: schema::onvif::IpType::IPv6,
+                i_pv_4_address: None,
+                i_pv_6_address: Some(address),
+            }),
+            None => Err(DeviceError::from(format!("DNS server \"{}\" is not a valid IPv4 or IPv6 address", address))),
+        }).collect::<Result<Vec<_>, DeviceError>>()?;
+
+        schema::devicemgmt::set_dns(&self.devicemgmt, &schema::devicemgmt::SetDns {
+            from_dhcp,
+            search_domain,
+            dns_manual,
+        }).await?;
+
+        return Ok(());
+    }
+
+    pub async fn set_network_interface(&self, token: &str, enabled: Option<bool>, ipv4: Option<Ipv4Config>) -> Result<(), DeviceError> {
+        let i_pv_4 = ipv4.map(|config| match config {
+            Ipv4Config::Dhcp => schema::onvif::IPv4NetworkInterfaceSetConfiguration {
+                enabled: true,
+                manual: vec![],
+                dhcp: true,
+            },
+            Ipv4Config::Manual { address, prefix_length } => schema::onvif::IPv4NetworkInterfaceSetConfiguration {
+                enabled: true,
+                manual: vec![schema::onvif::PrefixedIPv4Address { address, prefix_length }],
+                dhcp: false,
+            },
+        });
+
+        schema::devicemgmt::set_network_interfaces(&self.devicemgmt, &schema::devicemgmt::SetNetworkInterfaces {
+            interface_token: schema::onvif::ReferenceToken(token.to_string()),
+            network_interface: schema::onvif::NetworkInterfaceSetConfiguration {
+                enabled,
+                i_pv_4,
+                ..Default::default()
+            },
+        }).await?;
+
+        return Ok(());
+    }
+
+    async fn get_stream_uris_v20(&self, media2: &soap::client::Client) -> Result<Vec<StreamInfo>, DeviceError> {
+        let profiles = schema::media2::get_profiles(media2, &Default::default()).await?.profiles;
+
+        let mut streams = Vec::new();
+        for profile in profiles {
+            let stream_uri = schema::media2::get_stream_uri(media2, &schema::media2::GetStreamUri {
+                protocol: "RTSP".into(),
+                profile_token: profile.token.clone(),
+            }).await?;
+
+            let video = profile.configurations.video_encoder.as_ref();
+            streams.push(StreamInfo {
+                profile_token: profile.token.0.clone(),
+                profile_name: profile.name.clone(),
+                encoding: video.map(|v| format!("{:?}", v.encoding)),
+                resolution: video.map(|v| (v.resolution.width, v.resolution.height)),
+                framerate: video.and_then(|v| v.rate_control.as_ref()).map(|r| r.frame_rate_limit as f64),
+                bitrate: video.and_then(|v| v.rate_control.as_ref()).map(|r| r.bitrate_limit),
+                uri: stream_uri.uri,
+            });
+        }
+
+        return Ok(streams);
+    }
+
 }
@@ -1,6 +1,7 @@
-use std::{net::{Ipv4Addr, SocketAddr, IpAddr}, time::Duration};
+use std::{collections::HashMap, net::{Ipv4Addr, SocketAddr, IpAddr}, time::Duration};
 
 use onvif::schema::ws_discovery::{probe, probe_matches};
+use serde::Serialize;
 use tokio::{net::UdpSocket, time};
 
 use crate::device::DeviceError;
@@ -8,7 +9,25 @@ use crate::device::DeviceError;
 // Adapted from https://github.com/lumeohq/onvif-rs/blob/main/onvif/examples/discovery.rs
 // Copyright (c) 2019 Lumeo, Inc.
 
-pub async fn discover() -> Result<(), DeviceError> {
+/// Interval between probe rounds in `--watch` mode.
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A device discovered via WS-Discovery, keyed by its endpoint UUID.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscoveredDevice {
+    pub uuid: String,
+    pub xaddrs: Vec<String>,
+    pub scopes: Vec<String>,
+    pub hardware: Option<String>,
+    pub name: Option<String>,
+    pub location: Option<String>,
+}
+
+/// Probe the network once and return discovered devices keyed by UUID.
+///
+/// A device answering on multiple local interfaces is merged into a single
+/// entry with its XAddrs combined.
+pub async fn discover() -> Result<HashMap<String, DiscoveredDevice>, DeviceError> {
     let local_ifaces = nix::ifaddrs::getifaddrs()
     .map_err(|err| format!("Could not get local IP addresses: {}", err))?;
 
@@ -28,6 +47,8 @@ pub async fn discover() -> Result<(), DeviceError> {
     })
     .filter( |ip| !ip.is_loopback() );
 
+    let mut devices: HashMap<String, DiscoveredDevice> = HashMap::new();
+
     for local_ipv4 in ipv4s {
         println!("Checking {} for cameras...", local_ipv4);
 
@@ -52,14 +73,103 @@ pub async fn discover() -> Result<(), DeviceError> {
                         .find_in_scopes("onvif://www.onvif.org")
                         .is_some()
                 });
-            
+
             for probe_match in envelope_iter {
-                println!("{:?}", probe_match);
+                let device = parse_probe_match(probe_match);
+                devices.entry(device.uuid.clone())
+                    .and_modify(|existing| merge_device(existing, &device))
+                    .or_insert(device);
             }
         }
     }
 
-    return Ok(());
+    return Ok(devices);
+}
+
+/// Probe on a fixed interval, printing devices as they appear and disappear.
+///
+/// Runs until interrupted or a probe round fails; the previous round's device map is kept so each
+/// round can be diffed against it instead of re-printing everything.
+pub async fn watch(interval: Duration) -> Result<(), DeviceError> {
+    let mut known: HashMap<String, DiscoveredDevice> = HashMap::new();
+
+    loop {
+        let current = discover().await?;
+
+        for (uuid, device) in &current {
+            if !known.contains_key(uuid) {
+                println!("+ {:#?}", device);
+            }
+        }
+        for (uuid, device) in &known {
+            if !current.contains_key(uuid) {
+                println!("- {} ({})", uuid, device.name.as_deref().unwrap_or("unknown"));
+            }
+        }
+
+        known = current;
+        time::sleep(interval).await;
+    }
+}
+
+/// Parse a `ProbeMatch` into a structured device, extracting the UUID from
+/// its endpoint reference and the hardware/name/location scopes.
+fn parse_probe_match(probe_match: &probe_matches::ProbeMatch) -> DiscoveredDevice {
+    let uuid = extract_uuid(&probe_match.endpoint_reference.address);
+
+    let xaddrs = probe_match.x_addrs
+        .split_whitespace()
+        .map(String::from)
+        .collect();
+
+    let scopes: Vec<String> = probe_match.scopes.as_ref()
+        .map(|scopes| scopes.text.split_whitespace().map(String::from).collect())
+        .unwrap_or_default();
+
+    let mut hardware = None;
+    let mut name = None;
+    let mut location = None;
+    for scope in &scopes {
+        if let Some(value) = scope.strip_prefix("onvif://www.onvif.org/hardware/") {
+            hardware = Some(value.to_string());
+        } else if let Some(value) = scope.strip_prefix("onvif://www.onvif.org/name/") {
+            name = Some(value.to_string());
+        } else if let Some(value) = scope.strip_prefix("onvif://www.onvif.org/location/") {
+            location = Some(value.to_string());
+        }
+    }
+
+    return DiscoveredDevice { uuid, xaddrs, scopes, hardware, name, location };
+}
+
+/// Extract the bare UUID from an endpoint reference address such as
+/// `urn:uuid:...` or `uuid:...`.
+fn extract_uuid(address: &str) -> String {
+    return address.rsplit(':').next().unwrap_or(address).to_string();
+}
+
+/// Merge a newly observed device into an existing entry, combining XAddrs
+/// and filling in scope fields the existing entry is missing.
+fn merge_device(existing: &mut DiscoveredDevice, new: &DiscoveredDevice) {
+    for xaddr in &new.xaddrs {
+        if !existing.xaddrs.contains(xaddr) {
+            existing.xaddrs.push(xaddr.clone());
+        }
+    }
+    for scope in &new.scopes {
+        if !existing.scopes.contains(scope) {
+            existing.scopes.push(scope.clone());
+        }
+    }
+    if existing.hardware.is_none() {
+        existing.hardware = new.hardware.clone();
+    }
+    if existing.name.is_none() {
+        existing.name = new.name.clone();
+    }
+    if existing.location.is_none() {
+        existing.location = new.location.clone();
+    }
 }
 
 async fn recv_string(s: &UdpSocket, timeout: Duration) -> tokio::io::Result<String> {
@@ -100,4 +210,4 @@ async fn send_probe(from_addr: Ipv4Addr) -> tokio::io::Result<UdpSocket> {
     socket.send_to(probe_xml.as_bytes(), multi_socket_addr).await?;
 
     return Ok(socket);
-}
\ No newline at end of file
+}
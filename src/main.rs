@@ -1,10 +1,15 @@
+use std::io::{self, Write};
+use std::os::unix::io::AsRawFd;
 use std::str::FromStr;
+use std::time::Duration;
 
 use chrono::{NaiveDateTime, NaiveDate, Utc};
 use log::*;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use nix::sys::termios;
+use serde::Serialize;
 
-use device::{Device, DeviceError};
+use device::{Device, DeviceError, Ipv4Config};
 use onvif::schema::onvif::User;
 use onvif::schema;
 use url::Url;
@@ -25,19 +30,45 @@ struct Cli {
     #[clap(long)]
     creds: Option<String>,
 
+    /// Output format.
+    #[clap(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+
     /// Subcommand.
     #[clap(subcommand)]
     command: Commands,
 }
 
+/// Output format for commands that support machine-readable output.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    /// Current free-form, human readable output.
+    Text,
+    /// Tab-separated columns.
+    Table,
+    /// JSON, suitable for piping into `jq` or other tooling.
+    Json,
+}
+
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Query network for all online ONVIF compatible devices.
-    Probe,
+    Probe {
+        /// Keep probing on an interval and print devices as they
+        /// appear/disappear instead of dumping the full result once.
+        #[clap(long)]
+        watch: bool,
+    },
+
+    /// Interactively create a credentials file entry.
+    Init,
 
     /// Show configuration for an ONVIF camera.
     Info,
 
+    /// List media profiles and their RTSP stream URLs.
+    Streams,
+
     /// Get a list of users from a camera.
     GetUsers,
     /// Set a user's password.
@@ -48,6 +79,87 @@ enum Commands {
 
     /// Reboot a camera.
     Reboot,
+
+    /// Configure NTP.
+    SetNtp {
+        /// Use DHCP-provided NTP servers instead of manual ones.
+        #[clap(long)]
+        dhcp: bool,
+        /// Manual NTP server hostname/address, repeatable (ignored with --dhcp).
+        #[clap(long = "server")]
+        servers: Vec<String>,
+    },
+
+    /// Configure DNS.
+    SetDns {
+        /// Use DHCP-provided DNS servers instead of manual ones.
+        #[clap(long)]
+        dhcp: bool,
+        /// Manual DNS server address, repeatable (ignored with --dhcp).
+        #[clap(long = "server")]
+        servers: Vec<String>,
+        /// Search domain, repeatable.
+        #[clap(long = "search-domain")]
+        search_domains: Vec<String>,
+    },
+
+    /// Enable/disable a network interface or change its IPv4 configuration.
+    SetNetworkInterface {
+        /// Interface token (`info` shows the interface name instead where one is set).
+        token: String,
+        /// Enable the interface.
+        #[clap(long, conflicts_with = "disable")]
+        enable: bool,
+        /// Disable the interface.
+        #[clap(long)]
+        disable: bool,
+        /// Switch the IPv4 configuration to DHCP.
+        #[clap(long, conflicts_with = "address")]
+        dhcp: bool,
+        /// Manual IPv4 address (requires --prefix-length).
+        #[clap(long, requires = "prefix_length")]
+        address: Option<String>,
+        /// Manual IPv4 prefix length (requires --address).
+        #[clap(long, requires = "address")]
+        prefix_length: Option<i32>,
+    },
+
+    /// Control pan/tilt/zoom.
+    Ptz {
+        /// Pan velocity, -1.0 to 1.0 (a translation with --relative, a position with --absolute).
+        #[clap(long, default_value = "0.0")]
+        pan: f32,
+        /// Tilt velocity, -1.0 to 1.0 (a translation with --relative, a position with --absolute).
+        #[clap(long, default_value = "0.0")]
+        tilt: f32,
+        /// Zoom velocity, -1.0 to 1.0 (a translation with --relative, a position with --absolute).
+        #[clap(long, default_value = "0.0")]
+        zoom: f32,
+        /// Speed multiplier applied to pan/tilt/zoom for a continuous move.
+        #[clap(long, default_value = "1.0")]
+        speed: f32,
+        /// Stop any ongoing PTZ movement.
+        #[clap(long)]
+        stop: bool,
+        /// Issue a relative move instead of a continuous move.
+        #[clap(long, conflicts_with = "absolute")]
+        relative: bool,
+        /// Issue an absolute move instead of a continuous move.
+        #[clap(long)]
+        absolute: bool,
+        /// Move to a preset by token instead of moving by pan/tilt/zoom.
+        #[clap(long)]
+        goto_preset: Option<String>,
+        /// Save the current position as a new preset with this name.
+        #[clap(long)]
+        set_preset: Option<String>,
+        /// Remove a preset by token.
+        #[clap(long)]
+        remove_preset: Option<String>,
+        /// Stop a continuous move after this many milliseconds.
+        #[clap(long)]
+        duration: Option<u64>,
+    },
 }
 
 /// Convert an ONVIF DateTime to chrono::NaiveDateTime.
@@ -71,6 +183,97 @@ fn network_host_to_string(network_host: &schema::onvif::NetworkHost) -> String {
     return display;
 }
 
+/// Parse a user-supplied NTP/DNS server value into a `NetworkHost`,
+/// routing it to the address field matching its actual type instead of
+/// assuming IPv4.
+fn parse_network_host(server: &str) -> schema::onvif::NetworkHost {
+    match util::classify_ip(server) {
+        Some(util::IpVersion::V4) => schema::onvif::NetworkHost {
+            type_: schema::onvif::NetworkHostType::IPv4,
+            dn_sname: None,
+            i_pv_4_address: Some(server.to_string()),
+            i_pv_6_address: None,
+        },
+        Some(util::IpVersion::V6) => schema::onvif::NetworkHost {
+            type_: schema::onvif::NetworkHostType::IPv6,
+            dn_sname: None,
+            i_pv_4_address: None,
+            i_pv_6_address: Some(server.to_string()),
+        },
+        None => schema::onvif::NetworkHost {
+            type_: schema::onvif::NetworkHostType::DNS,
+            dn_sname: Some(server.to_string()),
+            i_pv_4_address: None,
+            i_pv_6_address: None,
+        },
+    }
+}
+
+/// Serializable snapshot of `show_device_info`'s output, used for the
+/// `table`/`json` output formats.
+#[derive(Serialize)]
+struct DeviceInfoRecord {
+    serial_number: String,
+    manufacturer: String,
+    model: String,
+    firmware_version: String,
+    hardware_id: String,
+    utc_time: Option<String>,
+    local_time: Option<String>,
+    time_zone: Option<String>,
+    ntp_from_dhcp: bool,
+    ntp_manual: Vec<String>,
+    ntp_dhcp: Vec<String>,
+    interfaces: Vec<InterfaceRecord>,
+    users: Vec<UserRecord>,
+}
+
+#[derive(Serialize)]
+struct InterfaceRecord {
+    name: String,
+    enabled: bool,
+    hw_address: Option<String>,
+    mtu: Option<u32>,
+    addresses: Vec<String>,
+    ssids: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct UserRecord {
+    username: String,
+    user_level: String,
+}
+
+/// Print a `DeviceInfoRecord` as tab-separated columns or JSON.
+fn print_device_info_record(info: &DeviceInfoRecord, format: OutputFormat) {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(info).unwrap()),
+        OutputFormat::Table => {
+            println!("Serial\t{}", info.serial_number);
+            println!("Manufacturer\t{}", info.manufacturer);
+            println!("Model\t{}", info.model);
+            println!("Firmware\t{}", info.firmware_version);
+            println!("Hardware ID\t{}", info.hardware_id);
+            println!("UTC Time\t{}", info.utc_time.as_deref().unwrap_or(""));
+            println!("Local Time\t{}", info.local_time.as_deref().unwrap_or(""));
+            println!("Time Zone\t{}", info.time_zone.as_deref().unwrap_or(""));
+            println!("NTP From DHCP\t{}", info.ntp_from_dhcp);
+            println!("NTP (manual)\t{}", info.ntp_manual.join(", "));
+            println!("NTP (DHCP)\t{}", info.ntp_dhcp.join(", "));
+            for iface in &info.interfaces {
+                println!("Interface\t{}\t{}\t{}\t{}\t{}\t{}",
+                    iface.name, iface.enabled, iface.hw_address.as_deref().unwrap_or(""),
+                    iface.mtu.map(|mtu| mtu.to_string()).unwrap_or_default(),
+                    iface.addresses.join(", "), iface.ssids.join(", "));
+            }
+            for user in &info.users {
+                println!("User\t{}\t{}", user.username, user.user_level);
+            }
+        },
+        OutputFormat::Text => unreachable!("text format is printed inline in show_device_info"),
+    }
+}
+
 /*async fn show_device_capabilities(device: &Device) -> Result<(), DeviceError> {
     let devicemgmt = device.get_device_service();
     let caps = schema::devicemgmt::get_capabilities(&devicemgmt, &Default::default()).await?.capabilities;
@@ -80,56 +283,64 @@ fn network_host_to_string(network_host: &schema::onvif::NetworkHost) -> String {
     return Ok(());
 }*/
 
-async fn show_device_info(device: &Device) -> Result<i32, DeviceError> {
+async fn show_device_info(device: &Device, format: OutputFormat) -> Result<i32, DeviceError> {
     let devicemgmt = device.get_device_service();
 
     // Device Info
     let info = schema::devicemgmt::get_device_information(&devicemgmt, &Default::default()).await?;
-    println!(
-        "Device Info:\n  Serial\t{}\n  Make\t\t{}\n  Model\t\t{}\n  Firmware\t{}\n  Hardware ID\t{}",
-        info.serial_number, info.manufacturer, info.model, info.firmware_version, info.hardware_id);
+    if format == OutputFormat::Text {
+        println!(
+            "Device Info:\n  Serial\t{}\n  Make\t\t{}\n  Model\t\t{}\n  Firmware\t{}\n  Hardware ID\t{}",
+            info.serial_number, info.manufacturer, info.model, info.firmware_version, info.hardware_id);
+    }
 
     // Device Time
     let time = schema::devicemgmt::get_system_date_and_time(&devicemgmt, &Default::default()).await?.system_date_and_time;
     let ntp = schema::devicemgmt::get_ntp(&devicemgmt, &Default::default()).await?.ntp_information;
-    println!("Device Time:");
-    println!("  Source\t{:?}", time.date_time_type);
-    println!("  DST\t\t{}", time.daylight_savings);
-    if let Some(tz) = time.time_zone {
-        println!("  TimeZone\t{}", tz.tz);
-    } else {
-        println!("  TimeZone\tNot Set");
-    }
-    if let Some(utc) = &time.utc_date_time {
-        print!("  UTC\t\t{}", datetime_to_naive(utc));
-        if datetime_to_naive(utc).signed_duration_since(Utc::now().naive_utc()).num_seconds().abs() > 15 {
-            println!(" *DOES NOT MATCH SYSTEM*");
+
+    let utc_time = time.utc_date_time.as_ref().map(|utc| datetime_to_naive(utc).to_string());
+    let local_time = time.local_date_time.as_ref().map(|local| datetime_to_naive(local).to_string());
+    let time_zone = time.time_zone.as_ref().map(|tz| tz.tz.clone());
+    let ntp_manual: Vec<String> = ntp.ntp_manual.iter().map(network_host_to_string).collect();
+    let ntp_dhcp: Vec<String> = ntp.ntp_from_dhcp.iter().map(network_host_to_string).collect();
+
+    if format == OutputFormat::Text {
+        println!("Device Time:");
+        println!("  Source\t{:?}", time.date_time_type);
+        println!("  DST\t\t{}", time.daylight_savings);
+        if let Some(tz) = &time_zone {
+            println!("  TimeZone\t{}", tz);
         } else {
-            println!("");
+            println!("  TimeZone\tNot Set");
+        }
+        if let Some(utc) = &time.utc_date_time {
+            print!("  UTC\t\t{}", datetime_to_naive(utc));
+            if datetime_to_naive(utc).signed_duration_since(Utc::now().naive_utc()).num_seconds().abs() > 15 {
+                println!(" *DOES NOT MATCH SYSTEM*");
+            } else {
+                println!("");
+            }
+        } else {
+            println!("  UTC\t\tNot Set");
+        }
+        if let Some(local) = &local_time {
+            println!("  Local\t\t{}", local);
+        } else {
+            println!("  Local\t\tNot Set");
         }
-    } else {
-        println!("  UTC\t\tNot Set");
-    }
-    if let Some(local) = &time.local_date_time {
-        println!("  Local\t\t{}", datetime_to_naive(local));
-    } else {
-        println!("  Local\t\tNot Set");
-    }
 
-    if ntp.from_dhcp {
-        print!("  DHCP NTP\t");
-        ntp.ntp_from_dhcp.into_iter().for_each(|ntp| print!("{}", network_host_to_string(&ntp)));
-        println!("");
+        if ntp.from_dhcp {
+            println!("  DHCP NTP\t{}", ntp_dhcp.join(""));
+        }
+        println!("  NTP\t\t{}", ntp_manual.join(""));
     }
-    print!("  NTP\t\t");
-    ntp.ntp_manual.into_iter().for_each(|ntp| print!("{}", network_host_to_string(&ntp)));
-    println!("");
-    
+
     // Device Capabilities
     //show_device_capabilities(&device).await?;
 
     // Network Configuration
     let network = schema::devicemgmt::get_network_interfaces(&devicemgmt, &Default::default()).await?.network_interfaces;
+    let mut interfaces = Vec::new();
     for iface in &network {
         let mut iface_name = iface.token.0.clone();
         if let Some(iface_info) = &iface.info {
@@ -137,49 +348,186 @@ async fn show_device_info(device: &Device) -> Result<i32, DeviceError> {
                 iface_name = name;
             }
         }
-        println!("Interface {} enabled={}", iface_name, iface.enabled);
-
-        if let Some(iface_info) = &iface.info {
-            println!("  HW Addr\t{}", iface_info.hw_address);
-            if let Some(mtu) = iface_info.mtu {
-                println!("  MTU\t\t{}", mtu);
-            }
-        }
 
+        let mut addresses = Vec::new();
         for ipv4_iface in &iface.i_pv_4 {
             if ipv4_iface.config.dhcp {
                 for dhcp_ipv4 in &ipv4_iface.config.from_dhcp {
-                    println!("  DHCP IP\t{}", dhcp_ipv4.address)
+                    addresses.push(dhcp_ipv4.address.clone());
                 }
             }
             for manual_ipv4 in &ipv4_iface.config.manual {
-                println!("  IP\t\t{}", manual_ipv4.address)
+                addresses.push(manual_ipv4.address.clone());
             }
         }
 
-        if let Some(extensions) = &iface.extension {
-            for dot11 in &extensions.dot_11 {
-                println!("  SSID\t\t{}", dot11.ssid)
+        if format == OutputFormat::Text {
+            println!("Interface {} enabled={}", iface_name, iface.enabled);
+
+            if let Some(iface_info) = &iface.info {
+                println!("  HW Addr\t{}", iface_info.hw_address);
+                if let Some(mtu) = iface_info.mtu {
+                    println!("  MTU\t\t{}", mtu);
+                }
+            }
+
+            for address in &addresses {
+                println!("  IP\t\t{}", address);
+            }
+
+            if let Some(extensions) = &iface.extension {
+                for dot11 in &extensions.dot_11 {
+                    println!("  SSID\t\t{}", dot11.ssid)
+                }
             }
         }
-    }
 
-    // RTSP Stream URLs
-    if let Some(media) = device.get_media_service() {
-        let _profiles = schema::media::get_profiles(&media, &Default::default()).await?.profiles;
-        //println!("{:#?}", profiles);
+        let ssids = iface.extension.as_ref()
+            .map(|extension| extension.dot_11.iter().map(|dot11| dot11.ssid.clone()).collect())
+            .unwrap_or_default();
+
+        interfaces.push(InterfaceRecord {
+            name: iface_name,
+            enabled: iface.enabled,
+            hw_address: iface.info.as_ref().map(|info| info.hw_address.clone()),
+            mtu: iface.info.as_ref().and_then(|info| info.mtu),
+            addresses,
+            ssids,
+        });
     }
 
     // User Configuration
     let users = device.get_users().await?;
-    println!("Users:");
-    for user in users {
-        println!("  User\t\t{} ({:?})", user.username, user.user_level);
+    if format == OutputFormat::Text {
+        println!("Users:");
+        for user in &users {
+            println!("  User\t\t{} ({:?})", user.username, user.user_level);
+        }
+    }
+    let users: Vec<UserRecord> = users.into_iter()
+        .map(|user| UserRecord { username: user.username, user_level: format!("{:?}", user.user_level) })
+        .collect();
+
+    if format != OutputFormat::Text {
+        let record = DeviceInfoRecord {
+            serial_number: info.serial_number,
+            manufacturer: info.manufacturer,
+            model: info.model,
+            firmware_version: info.firmware_version,
+            hardware_id: info.hardware_id,
+            utc_time,
+            local_time,
+            time_zone,
+            ntp_from_dhcp: ntp.from_dhcp,
+            ntp_manual,
+            ntp_dhcp,
+            interfaces,
+            users,
+        };
+        print_device_info_record(&record, format);
     }
 
     return Ok(0);
 }
 
+/// Prompt for a line of input on stdout, returning the trimmed response.
+fn prompt(message: &str) -> String {
+    print!("{}", message);
+    io::stdout().flush().ok();
+    let mut line = String::new();
+    io::stdin().read_line(&mut line).ok();
+    return line.trim().to_string();
+}
+
+/// Prompt for a line of input on stdout without echoing it to the
+/// terminal, for secrets like passwords. Falls back to a normal (echoed)
+/// prompt if stdin isn't a terminal whose settings can be read.
+fn prompt_password(message: &str) -> String {
+    let stdin_fd = io::stdin().as_raw_fd();
+    let original_termios = match termios::tcgetattr(stdin_fd) {
+        Ok(termios) => termios,
+        Err(_) => return prompt(message),
+    };
+
+    let mut silent_termios = original_termios.clone();
+    silent_termios.local_flags.remove(termios::LocalFlags::ECHO);
+    termios::tcsetattr(stdin_fd, termios::SetArg::TCSANOW, &silent_termios).ok();
+
+    print!("{}", message);
+    io::stdout().flush().ok();
+    let mut line = String::new();
+    io::stdin().read_line(&mut line).ok();
+    println!();
+
+    termios::tcsetattr(stdin_fd, termios::SetArg::TCSANOW, &original_termios).ok();
+
+    return line.trim().to_string();
+}
+
+/// Interactively collect credentials and the camera(s) they apply to, then
+/// validate and append them to the credentials file.
+async fn run_init_wizard(cli: &Cli) -> Result<i32, DeviceError> {
+    let creds_path = match &cli.creds {
+        Some(path) => path.clone(),
+        None => {
+            let path = prompt("Credentials file [credentials.json]: ");
+            if path.is_empty() { "credentials.json".to_string() } else { path }
+        },
+    };
+
+    let username = prompt("Username: ");
+    let password = prompt_password("Password: ");
+
+    let mut candidate_uris: Vec<Url> = Vec::new();
+    if let Some(uri) = &cli.uri {
+        candidate_uris.push(Url::from_str(uri).map_err(|_| String::from("Could not parse URI"))?);
+    } else if prompt("Run a discovery probe to pick a camera? [y/N]: ").eq_ignore_ascii_case("y") {
+        println!("Discovering cameras");
+        let devices: Vec<_> = discovery::discover().await?.into_values().collect();
+        if devices.is_empty() {
+            println!("No cameras found.");
+            return Ok(-1);
+        }
+        for (index, device) in devices.iter().enumerate() {
+            println!("  [{}] {} ({})", index, device.name.as_deref().unwrap_or("unknown"), device.uuid);
+        }
+
+        let selection = prompt("Select camera(s), comma separated: ");
+        for index in selection.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+            let index: usize = index.parse().map_err(|_| format!("Invalid selection: {}", index))?;
+            let device = devices.get(index).ok_or_else(|| format!("No such camera: {}", index))?;
+            let xaddr = device.xaddrs.first()
+                .ok_or_else(|| format!("Camera {} has no service address", device.uuid))?;
+            candidate_uris.push(Url::parse(xaddr).map_err(|err| err.to_string())?);
+        }
+
+        if candidate_uris.is_empty() {
+            println!("No camera selected.");
+            return Ok(-1);
+        }
+    } else {
+        let uri = prompt("Camera URI: ");
+        candidate_uris.push(Url::from_str(&uri).map_err(|_| String::from("Could not parse URI"))?);
+    }
+
+    let mut serials = Vec::new();
+    for uri in &candidate_uris {
+        let device = Device::new(uri.clone(), Some(username.clone()), Some(password.clone())).await?;
+        let info = schema::devicemgmt::get_device_information(&device.get_device_service(), &Default::default()).await?;
+        println!("Validated against {} {} ({})", info.manufacturer, info.model, info.serial_number);
+        serials.push(info.serial_number);
+    }
+
+    util::save_credentials(&creds_path, util::Credentials {
+        user: username,
+        pass: password,
+        serial: serials,
+    }).map_err(|err| format!("Could not save credentials: {}", err))?;
+
+    println!("Saved credentials to {}", creds_path);
+    return Ok(0);
+}
+
 async fn run_command(cli: &Cli) -> Result<i32, DeviceError> {
     let uri = match &cli.uri {
         Some(uri) => {
@@ -201,13 +549,29 @@ async fn run_command(cli: &Cli) -> Result<i32, DeviceError> {
     let device = Device::new(uri, user, pass).await?;
 
     match &cli.command {
-        Commands::Probe => unreachable!(),
+        Commands::Probe { .. } => unreachable!(),
+        Commands::Init => unreachable!(),
 
         Commands::GetUsers => {
             let users = device.get_users().await?;
-            for user in users {
-                println!("Users:");
-                println!("    {}\t{:?}\t{:?}", user.username, user.user_level, user.extension)
+            match cli.format {
+                OutputFormat::Text => {
+                    for user in &users {
+                        println!("Users:");
+                        println!("    {}\t{:?}\t{:?}", user.username, user.user_level, user.extension)
+                    }
+                },
+                OutputFormat::Table => {
+                    for user in &users {
+                        println!("{}\t{:?}", user.username, user.user_level);
+                    }
+                },
+                OutputFormat::Json => {
+                    let records: Vec<UserRecord> = users.into_iter()
+                        .map(|user| UserRecord { username: user.username, user_level: format!("{:?}", user.user_level) })
+                        .collect();
+                    println!("{}", serde_json::to_string_pretty(&records).unwrap());
+                },
             }
         },
 
@@ -236,7 +600,93 @@ async fn run_command(cli: &Cli) -> Result<i32, DeviceError> {
             println!("Reboot: {}", message);
         },
 
-        Commands::Info => return Ok(show_device_info(&device).await?),
+        Commands::SetNtp { dhcp, servers } => {
+            let ntp_manual: Vec<schema::onvif::NetworkHost> = servers.iter()
+                .map(|server| parse_network_host(server))
+                .collect();
+
+            device.set_ntp(*dhcp, ntp_manual.clone()).await?;
+
+            println!("NTP updated, from_dhcp={}", dhcp);
+            for host in &ntp_manual {
+                println!("  {}", network_host_to_string(host));
+            }
+        },
+
+        Commands::SetDns { dhcp, servers, search_domains } => {
+            device.set_dns(*dhcp, search_domains.clone(), servers.clone()).await?;
+
+            println!("DNS updated, from_dhcp={}", dhcp);
+            for server in servers {
+                println!("  {}", server);
+            }
+        },
+
+        Commands::SetNetworkInterface { token, enable, disable, dhcp, address, prefix_length } => {
+            let enabled = if *enable { Some(true) } else if *disable { Some(false) } else { None };
+            let ipv4 = if *dhcp {
+                Some(Ipv4Config::Dhcp)
+            } else if let (Some(address), Some(prefix_length)) = (address, prefix_length) {
+                Some(Ipv4Config::Manual { address: address.clone(), prefix_length: *prefix_length })
+            } else {
+                None
+            };
+
+            device.set_network_interface(token, enabled, ipv4).await?;
+            println!("Interface {} updated.", token);
+        },
+
+        Commands::Ptz { pan, tilt, zoom, speed, stop, relative, absolute, goto_preset, set_preset, remove_preset, duration } => {
+            if *stop {
+                device.ptz_stop().await?;
+                println!("PTZ stopped.");
+            } else if let Some(preset_token) = remove_preset {
+                device.ptz_remove_preset(preset_token).await?;
+                println!("Removed preset {}", preset_token);
+            } else if let Some(preset_name) = set_preset {
+                let preset_token = device.ptz_set_preset(preset_name).await?;
+                println!("Saved preset \"{}\" as {}", preset_name, preset_token);
+            } else if let Some(preset_token) = goto_preset {
+                device.ptz_goto_preset(preset_token).await?;
+                println!("Moving to preset {}", preset_token);
+            } else if *relative {
+                device.ptz_relative_move(*pan, *tilt, *zoom).await?;
+                println!("Relative move issued.");
+            } else if *absolute {
+                device.ptz_absolute_move(*pan, *tilt, *zoom).await?;
+                println!("Absolute move issued.");
+            } else {
+                device.ptz_continuous_move(*pan, *tilt, *zoom, *speed).await?;
+                match duration {
+                    Some(duration) => {
+                        tokio::time::sleep(Duration::from_millis(*duration)).await;
+                        device.ptz_stop().await?;
+                        println!("Continuous move issued for {}ms.", duration);
+                    },
+                    None => println!("Continuous move issued; use --stop to halt."),
+                }
+            }
+        },
+
+        Commands::Streams => {
+            let streams = device.get_stream_uris().await?;
+            for stream in streams {
+                println!("Profile {} ({})", stream.profile_name, stream.profile_token);
+                println!("  Codec\t\t{}", stream.encoding.as_deref().unwrap_or("Unknown"));
+                if let Some((width, height)) = stream.resolution {
+                    println!("  Resolution\t{}x{}", width, height);
+                }
+                if let Some(framerate) = stream.framerate {
+                    println!("  Framerate\t{} fps", framerate);
+                }
+                if let Some(bitrate) = stream.bitrate {
+                    println!("  Bitrate\t{} kbps", bitrate);
+                }
+                println!("  RTSP URL\t{}", stream.uri);
+            }
+        },
+
+        Commands::Info => return Ok(show_device_info(&device, cli.format).await?),
     }
 
     return Ok(0);
@@ -248,12 +698,47 @@ async fn main() {
     let cli = Cli::parse();
 
     match &cli.command {
-        Commands::Probe => {
+        Commands::Probe { watch } => {
             println!("Discovering cameras");
-            let result = discovery::discover().await;
-            match result {
-                Ok(_) => {},
-                Err(err) => println!("Error: {}", err),
+            if *watch {
+                if let Err(err) = discovery::watch(discovery::WATCH_INTERVAL).await {
+                    println!("Error: {}", err);
+                }
+            } else {
+                match discovery::discover().await {
+                    Ok(devices) => match cli.format {
+                        OutputFormat::Text => {
+                            for device in devices.values() {
+                                println!("{:#?}", device);
+                            }
+                        },
+                        OutputFormat::Table => {
+                            for device in devices.values() {
+                                println!("{}\t{}\t{}\t{}\t{}",
+                                    device.uuid,
+                                    device.name.as_deref().unwrap_or(""),
+                                    device.hardware.as_deref().unwrap_or(""),
+                                    device.location.as_deref().unwrap_or(""),
+                                    device.xaddrs.join(", "));
+                            }
+                        },
+                        OutputFormat::Json => {
+                            let devices: Vec<_> = devices.values().collect();
+                            println!("{}", serde_json::to_string_pretty(&devices).unwrap());
+                        },
+                    },
+                    Err(err) => println!("Error: {}", err),
+                }
+            }
+        },
+
+        Commands::Init => {
+            match run_init_wizard(&cli).await {
+                Ok(status) => std::process::exit(status),
+                Err(err) => {
+                    error!("Error: {}", err);
+                    std::process::exit(-2);
+                }
             }
         },
 
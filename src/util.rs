@@ -1,13 +1,33 @@
 use std::{io, fs};
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
 
 use serde::{Serialize, Deserialize};
 
+/// Which IP address family a user-supplied host string parses as.
+pub enum IpVersion {
+    V4,
+    V6,
+}
+
+/// Classify a user-supplied address as IPv4, IPv6, or neither (e.g. a
+/// hostname), without assuming IPv4 by default.
+pub fn classify_ip(address: &str) -> Option<IpVersion> {
+    if Ipv4Addr::from_str(address).is_ok() {
+        return Some(IpVersion::V4);
+    }
+    if Ipv6Addr::from_str(address).is_ok() {
+        return Some(IpVersion::V6);
+    }
+    return None;
+}
+
 #[derive(Serialize, Deserialize)]
-struct Credentials {
-    user: String,
-    pass: String,
+pub struct Credentials {
+    pub user: String,
+    pub pass: String,
     #[serde(default)]
-    serial: Vec<String>,
+    pub serial: Vec<String>,
 }
 
 type CredentialsFile = Vec<Credentials>;
@@ -29,3 +49,20 @@ pub fn load_credentials(path: &str, serial: Option<String>) -> io::Result<Option
 
     return Ok(None);
 }
+
+/// Append a credentials entry to a credentials file, creating it if it
+/// doesn't exist yet.
+pub fn save_credentials(path: &str, entry: Credentials) -> io::Result<()> {
+    let mut cred_file: CredentialsFile = match fs::read_to_string(path) {
+        Ok(cred_json) => serde_json::from_str(&cred_json)?,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(err),
+    };
+
+    cred_file.push(entry);
+
+    let cred_json = serde_json::to_string_pretty(&cred_file)?;
+    fs::write(path, cred_json)?;
+
+    return Ok(());
+}